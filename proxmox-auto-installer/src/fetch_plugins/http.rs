@@ -1,16 +1,20 @@
-use anyhow::{bail, Error, Result};
+use anyhow::{bail, Context, Error, Result};
+use hickory_resolver::{system_conf::read_system_conf, Resolver};
 use log::info;
 use std::{
     fs::{self, read_to_string},
     path::Path,
-    process::Command,
 };
 
-use crate::fetch_plugins::utils::{post, sysinfo};
+use crate::fetch_plugins::utils::{
+    config::{FetchConfig, HttpConfig},
+    post, sysinfo,
+};
 
 use super::utils;
 
 static CERT_FINGERPRINT_FILE: &str = "cert_fingerprint.txt";
+static ANSWER_FILE: &str = "answer.toml";
 static ANSWER_SUBDOMAIN: &str = "proxmoxinst";
 static ANSWER_SUBDOMAIN_FP: &str = "proxmoxinst-fp";
 
@@ -42,6 +46,29 @@ impl FetchFromHTTP {
     /// be placed in a `cert_fingerprint.txt` file in the `proxmoxinst` partition, as DHCP option,
     /// or as DNS TXT record. If provided, the `cert_fingerprint.txt` file has preference.
     pub fn get_answer() -> Result<String> {
+        Self::get_answer_impl(None)
+    }
+
+    /// Same as [`Self::get_answer`], but with a pre-set URL/fingerprint coming from the ISO's
+    /// fetch configuration. When a URL is already known, DHCP/DNS discovery is skipped entirely.
+    pub fn get_answer_with_config(http_config: &HttpConfig) -> Result<String> {
+        Self::get_answer_impl(Some(http_config))
+    }
+
+    /// Reads the answer file straight off the `proxmoxinst` partition, without going through
+    /// HTTP at all.
+    pub fn get_answer_from_partition() -> Result<String> {
+        let mount_path = utils::mount_proxmoxinst_part()?;
+        let answer_path = Path::new(mount_path.as_str()).join(ANSWER_FILE);
+        fs::read_to_string(&answer_path).map_err(|err| {
+            Error::msg(format!(
+                "could not read answer file at '{}': {err}",
+                answer_path.display()
+            ))
+        })
+    }
+
+    fn get_answer_impl(preset: Option<&HttpConfig>) -> Result<String> {
         info!("Checking for certificate fingerprint in file.");
         let mut fingerprint: Option<String> = match Self::get_cert_fingerprint_from_file() {
             Ok(fp) => Some(fp),
@@ -53,13 +80,24 @@ impl FetchFromHTTP {
 
         let answer_url: String;
 
-        (answer_url, fingerprint) = match Self::fetch_dhcp(fingerprint.clone()) {
-            Ok((url, fp)) => (url, fp),
-            Err(err) => {
-                info!("{err}");
-                Self::fetch_dns(fingerprint.clone())?
-            }
-        };
+        if let Some(fp) = preset.and_then(|c| c.cert_fingerprint.clone()) {
+            fingerprint = Some(fp);
+        }
+
+        if let Some(url) = preset.and_then(|c| c.url.clone()) {
+            info!(
+                "Using preset HTTP fetch URL from ISO configuration, skipping DHCP/DNS discovery."
+            );
+            answer_url = url;
+        } else {
+            (answer_url, fingerprint) = match Self::fetch_dhcp(fingerprint.clone()) {
+                Ok((url, fp)) => (url, fp),
+                Err(err) => {
+                    info!("{err}");
+                    Self::fetch_dns(fingerprint.clone())?
+                }
+            };
+        }
 
         if fingerprint.is_some() {
             let fp = fingerprint.clone();
@@ -102,89 +140,89 @@ impl FetchFromHTTP {
         Err(Error::msg("Could not find search domain in resolv.conf."))
     }
 
-    /// Runs a TXT DNS query on the domain provided
-    fn query_txt_record(query: String) -> Result<String> {
+    /// Builds an in-process resolver from `/etc/resolv.conf`. When `dnssec` is set, the DO bit
+    /// is set on every query and responses are validated against the built-in root trust anchor.
+    fn build_resolver(dnssec: bool) -> Result<Resolver> {
+        let (config, mut opts) = read_system_conf()
+            .map_err(|err| Error::msg(format!("could not read /etc/resolv.conf: {err}")))?;
+        opts.validate = dnssec;
+        Resolver::new(config, opts)
+            .map_err(|err| Error::msg(format!("could not set up DNS resolver: {err}")))
+    }
+
+    /// Runs a TXT DNS query on the domain provided. When `dnssec` is set, the resolver already
+    /// validates the RRSIG chain against the root trust anchor before returning a successful
+    /// lookup (an unvalidated or tampered answer surfaces as an `Err` instead), so reaching the
+    /// `Ok` arm below is itself the Authenticated Data guarantee.
+    ///
+    /// Note that hickory-resolver 0.24 does not implement NSEC/NSEC3 validation (its
+    /// `valid_nsec` is hard-coded `false` in `caching_client::handle_nxdomain`), so a negative
+    /// answer (NXDOMAIN/NODATA) can never come back as DNSSEC-authenticated with this resolver
+    /// version; it is treated the same as any other lookup failure below.
+    fn query_txt_record(resolver: &Resolver, query: String) -> Result<String> {
         info!("Querying TXT record for '{query}'");
-        let url: String;
-        match Command::new("dig")
-            .args(["txt", "+short"])
-            .arg(&query)
-            .output()
-        {
-            Ok(output) => {
-                if output.status.success() {
-                    url = String::from_utf8(output.stdout)?
-                        .replace('"', "")
-                        .trim()
-                        .into();
-                    if url.is_empty() {
-                        bail!("Got empty response.");
-                    }
-                } else {
-                    bail!(
-                        "Error querying DNS record '{query}' : {}",
-                        String::from_utf8(output.stderr)?
-                    );
-                }
-            }
-            Err(err) => bail!("Error querying DNS record '{query}': {err}"),
-        }
-        info!("Found: '{url}'");
-        Ok(url)
+
+        let lookup = resolver
+            .txt_lookup(&query)
+            .with_context(|| format!("Error querying DNS record '{query}'"))?;
+
+        let txt = lookup
+            .iter()
+            .next()
+            .map(|record| record.to_string().replace('"', ""))
+            .filter(|txt| !txt.is_empty())
+            .ok_or_else(|| Error::msg(format!("Got empty response for '{query}'.")))?;
+
+        // Every call issues its own lookup: the fingerprint record queried in `fetch_dns` is a
+        // different name and is validated separately, not taken from this lookup.
+        info!("Found: '{txt}'");
+        Ok(txt)
     }
 
     /// Tries to fetch answer URL and SSL fingerprint info from DNS
     fn fetch_dns(mut fingerprint: Option<String>) -> Result<(String, Option<String>)> {
         let search_domain = Self::get_search_domain()?;
+        let dnssec = FetchConfig::from_iso()?.dns.dnssec;
+        let resolver = Self::build_resolver(dnssec)?;
 
-        let answer_url = match Self::query_txt_record(format!("{ANSWER_SUBDOMAIN}.{search_domain}"))
-        {
+        let answer_url = match Self::query_txt_record(
+            &resolver,
+            format!("{ANSWER_SUBDOMAIN}.{search_domain}"),
+        ) {
             Ok(url) => url,
             Err(err) => bail!("{err}"),
         };
 
         if fingerprint.is_none() {
-            fingerprint =
-                match Self::query_txt_record(format!("{ANSWER_SUBDOMAIN_FP}.{search_domain}")) {
-                    Ok(fp) => Some(fp),
-                    Err(err) => {
-                        info!("{err}");
-                        None
-                    }
-                };
+            fingerprint = match Self::query_txt_record(
+                &resolver,
+                format!("{ANSWER_SUBDOMAIN_FP}.{search_domain}"),
+            ) {
+                Ok(fp) => Some(fp),
+                Err(err) => {
+                    info!("{err}");
+                    None
+                }
+            };
         }
         Ok((answer_url, fingerprint))
     }
 
-    /// Tries to fetch answer URL and SSL fingerprint info from DHCP options
+    /// Tries to fetch answer URL and SSL fingerprint info from DHCP options, considering only
+    /// the newest non-expired lease of an interface we actually brought up.
     fn fetch_dhcp(mut fingerprint: Option<String>) -> Result<(String, Option<String>)> {
-        let leases = fs::read_to_string(DHCP_LEASE_FILE)?;
-
-        let mut answer_url: Option<String> = None;
+        let nics = utils::get_nic_list()?;
+        let options = utils::dhcp_leases::fetch_options(DHCP_LEASE_FILE, &nics)?;
 
-        let url_match = format!("option {DHCP_URL_OPTION}");
-        let fp_match = format!("option {DHCP_FP_OPTION}");
-
-        for line in leases.lines() {
-            if answer_url.is_none() && line.trim().starts_with(url_match.as_str()) {
-                answer_url = Self::strip_dhcp_option(line.split(' ').nth_back(0));
-            }
-            if fingerprint.is_none() && line.trim().starts_with(fp_match.as_str()) {
-                fingerprint = Self::strip_dhcp_option(line.split(' ').nth_back(0));
-            }
-        }
-
-        let answer_url = match answer_url {
+        let answer_url = match options.get(DHCP_URL_OPTION) {
+            Some(url) => url.clone(),
             None => bail!("No DHCP option found for fetch URL."),
-            Some(url) => url,
         };
 
-        Ok((answer_url, fingerprint))
-    }
+        if fingerprint.is_none() {
+            fingerprint = options.get(DHCP_FP_OPTION).cloned();
+        }
 
-    /// Clean DHCP option string
-    fn strip_dhcp_option(value: Option<&str>) -> Option<String> {
-        // value is expected to be in format: "value";
-        value.map(|value| String::from(&value[1..value.len() - 2]))
+        Ok((answer_url, fingerprint))
     }
 }
@@ -0,0 +1,35 @@
+use anyhow::Result;
+use log::info;
+
+use utils::config::{FetchConfig, FetchMode};
+
+pub mod http;
+pub mod utils;
+
+use http::FetchFromHTTP;
+
+/// Fetches the answer file, honoring the fetch-mode configuration baked into the ISO, if any.
+///
+/// Without a configuration file (or with `FetchMode::Auto`), this keeps the historical
+/// fallback chain: partition fingerprint file, then DHCP, then DNS. An explicit mode restricts
+/// the installer to a single strategy, e.g. to pin an ISO to a known HTTP endpoint without
+/// relying on a second USB stick for the URL/fingerprint.
+pub fn get_answer() -> Result<String> {
+    let config = FetchConfig::from_iso()?;
+
+    match config.mode {
+        FetchMode::Auto => FetchFromHTTP::get_answer(),
+        FetchMode::Included => {
+            info!("Fetch mode 'included': loading answer file bundled on the ISO.");
+            utils::get_included_answer()
+        }
+        FetchMode::Partition => {
+            info!("Fetch mode 'partition': loading answer file from the proxmoxinst partition.");
+            FetchFromHTTP::get_answer_from_partition()
+        }
+        FetchMode::Http => {
+            info!("Fetch mode 'http': fetching answer file via HTTP.");
+            FetchFromHTTP::get_answer_with_config(&config.http)
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::{fs, path::Path};
+
+/// Location of the optional fetch-mode configuration, baked into the installer ISO.
+static FETCH_CONFIG_FILE: &str = "/auto-installer-mode.toml";
+
+/// Selects which answer-fetching strategy the installer should use.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FetchMode {
+    /// Keep today's fallback chain: partition, then DHCP, then DNS.
+    #[default]
+    Auto,
+    /// Load the answer file bundled directly on the ISO.
+    Included,
+    /// Only look for the answer file on the `proxmoxinst` partition.
+    Partition,
+    /// Only fetch the answer file via HTTP POST.
+    Http,
+}
+
+/// Pre-set HTTP fetch parameters, used to avoid DHCP/DNS discovery when already known.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct HttpConfig {
+    pub url: Option<String>,
+    pub cert_fingerprint: Option<String>,
+}
+
+/// DNS discovery parameters.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct DnsConfig {
+    /// Require DNSSEC-authenticated TXT records, rejecting unsigned or unvalidated answers.
+    #[serde(default)]
+    pub dnssec: bool,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct FetchConfig {
+    #[serde(default)]
+    pub mode: FetchMode,
+    #[serde(default)]
+    pub http: HttpConfig,
+    #[serde(default)]
+    pub dns: DnsConfig,
+}
+
+impl FetchConfig {
+    /// Reads the fetch configuration baked into the ISO, if present.
+    ///
+    /// ISOs built without this file keep today's behavior, as if `mode = "auto"` had been set.
+    pub fn from_iso() -> Result<Self> {
+        Self::from_file(FETCH_CONFIG_FILE)
+    }
+
+    fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("could not read fetch config at '{}'", path.display()))?;
+        toml::from_str(&contents)
+            .with_context(|| format!("could not parse fetch config at '{}'", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Writes `contents` to a fresh file under the OS temp dir and returns its path; the file is
+    /// removed again when the returned guard is dropped.
+    struct TempConfigFile(std::path::PathBuf);
+
+    impl TempConfigFile {
+        fn new(contents: &str) -> Self {
+            static COUNTER: AtomicU32 = AtomicU32::new(0);
+            let path = std::env::temp_dir().join(format!(
+                "auto-installer-mode-test-{}-{}.toml",
+                std::process::id(),
+                COUNTER.fetch_add(1, Ordering::Relaxed)
+            ));
+            fs::write(&path, contents).unwrap();
+            Self(path)
+        }
+    }
+
+    impl Drop for TempConfigFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn missing_file_falls_back_to_default() {
+        let config = FetchConfig::from_file("/nonexistent/auto-installer-mode.toml").unwrap();
+        assert_eq!(config.mode, FetchMode::Auto);
+    }
+
+    #[test]
+    fn empty_file_uses_defaults_for_every_section() {
+        let file = TempConfigFile::new("");
+        let config = FetchConfig::from_file(&file.0).unwrap();
+        assert_eq!(config.mode, FetchMode::Auto);
+        assert_eq!(config.http.url, None);
+        assert!(!config.dns.dnssec);
+    }
+
+    #[test]
+    fn parses_mode_and_nested_sections() {
+        let file = TempConfigFile::new(
+            r#"
+            mode = "http"
+
+            [http]
+            url = "https://example.com/answer.toml"
+            cert_fingerprint = "aa:bb"
+
+            [dns]
+            dnssec = true
+            "#,
+        );
+        let config = FetchConfig::from_file(&file.0).unwrap();
+        assert_eq!(config.mode, FetchMode::Http);
+        assert_eq!(
+            config.http.url.as_deref(),
+            Some("https://example.com/answer.toml")
+        );
+        assert_eq!(config.http.cert_fingerprint.as_deref(), Some("aa:bb"));
+        assert!(config.dns.dnssec);
+    }
+
+    #[test]
+    fn rejects_unknown_mode() {
+        let file = TempConfigFile::new(r#"mode = "bogus""#);
+        assert!(FetchConfig::from_file(&file.0).is_err());
+    }
+}
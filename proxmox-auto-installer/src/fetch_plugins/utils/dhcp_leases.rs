@@ -0,0 +1,242 @@
+use anyhow::Result;
+use std::{
+    collections::HashMap,
+    fs,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// A single `lease { ... }` block parsed out of a dhclient leases file.
+#[derive(Debug, Default)]
+struct Lease {
+    interface: Option<String>,
+    /// Seconds since the epoch. `None` covers both a missing `expire` line and `expire never;`.
+    expire: Option<u64>,
+    renew: Option<u64>,
+    options: HashMap<String, String>,
+}
+
+/// Reads the dhclient leases file and returns the DHCP options of the newest non-expired lease
+/// for one of `nic_names`, or an empty map if no such lease exists.
+///
+/// dhclient appends a new `lease { ... }` block every time it renews, so a leases file can
+/// contain several stale blocks for interfaces that roamed onto a different network; only the
+/// most recent still-valid block is considered.
+pub fn fetch_options(path: &str, nic_names: &[String]) -> Result<HashMap<String, String>> {
+    let content = fs::read_to_string(path)?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    Ok(select_options(&content, nic_names, now))
+}
+
+/// Picks the options of the newest non-expired lease for one of `nic_names` as of `now` (seconds
+/// since the epoch), out of all `lease { ... }` blocks in `content`.
+fn select_options(content: &str, nic_names: &[String], now: u64) -> HashMap<String, String> {
+    let leases = parse_leases(content);
+
+    let mut best: Option<&Lease> = None;
+    for lease in &leases {
+        let Some(interface) = lease.interface.as_deref() else {
+            continue;
+        };
+        if !nic_names.iter().any(|nic| nic == interface) {
+            continue;
+        }
+        if lease.expire.is_some_and(|expire| expire <= now) {
+            continue;
+        }
+
+        let candidate_age = lease.renew.or(lease.expire).unwrap_or(0);
+        let is_newer = match best {
+            None => true,
+            Some(current) => candidate_age > current.renew.or(current.expire).unwrap_or(0),
+        };
+        if is_newer {
+            best = Some(lease);
+        }
+    }
+
+    best.map(|lease| lease.options.clone()).unwrap_or_default()
+}
+
+/// Splits the leases file into `lease { ... }` blocks and parses each one.
+fn parse_leases(content: &str) -> Vec<Lease> {
+    let mut leases = Vec::new();
+    let mut current: Option<Lease> = None;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if current.is_none() {
+            if line.starts_with("lease ") && line.ends_with('{') {
+                current = Some(Lease::default());
+            }
+            continue;
+        }
+
+        if line == "}" {
+            if let Some(lease) = current.take() {
+                leases.push(lease);
+            }
+            continue;
+        }
+
+        let lease = current.as_mut().expect("lease block is open");
+        if let Some(value) = line.strip_prefix("interface ") {
+            lease.interface = decode_option_value(value);
+        } else if let Some(value) = line.strip_prefix("expire ") {
+            lease.expire = parse_timestamp(value);
+        } else if let Some(value) = line.strip_prefix("renew ") {
+            lease.renew = parse_timestamp(value);
+        } else if let Some((name, value)) = line
+            .strip_prefix("option ")
+            .and_then(|rest| rest.split_once(' '))
+            .and_then(|(name, value)| decode_option_value(value).map(|value| (name, value)))
+        {
+            lease.options.insert(name.to_string(), value);
+        }
+    }
+
+    leases
+}
+
+/// Decodes a dhclient option value, handling both the quoted-text form (`"http://..."`) and the
+/// colon-separated hex form dhclient emits for options it has no text definition for.
+fn decode_option_value(raw: &str) -> Option<String> {
+    let raw = raw.trim().trim_end_matches(';').trim();
+
+    if let Some(text) = raw.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+        return Some(text.to_string());
+    }
+
+    if raw.contains(':') {
+        let bytes: Option<Vec<u8>> = raw
+            .split(':')
+            .map(|byte| u8::from_str_radix(byte, 16).ok())
+            .collect();
+        if let Some(text) = bytes.and_then(|bytes| String::from_utf8(bytes).ok()) {
+            return Some(text);
+        }
+    }
+
+    None
+}
+
+/// Parses an `expire`/`renew` value (`never;` or `<weekday> YYYY/MM/DD HH:MM:SS UTC;`) into
+/// seconds since the epoch. Returns `None` for `never`, meaning the lease does not expire.
+fn parse_timestamp(raw: &str) -> Option<u64> {
+    let raw = raw.trim().trim_end_matches(';').trim();
+    if raw == "never" {
+        return None;
+    }
+
+    let mut parts = raw.split_whitespace();
+    let _weekday = parts.next()?;
+    let date = parts.next()?;
+    let time = parts.next()?;
+
+    let mut date_parts = date.split('/');
+    let year: i64 = date_parts.next()?.parse().ok()?;
+    let month: i64 = date_parts.next()?.parse().ok()?;
+    let day: i64 = date_parts.next()?.parse().ok()?;
+
+    let mut time_parts = time.split(':');
+    let hour: i64 = time_parts.next()?.parse().ok()?;
+    let minute: i64 = time_parts.next()?.parse().ok()?;
+    let second: i64 = time_parts.next()?.parse().ok()?;
+
+    let days = days_from_civil(year, month, day);
+    let seconds = days * 86400 + hour * 3600 + minute * 60 + second;
+    u64::try_from(seconds).ok()
+}
+
+/// Howard Hinnant's `days_from_civil`: days since the epoch (1970-01-01) for a Gregorian date.
+fn days_from_civil(y: i64, m: i64, d: i64) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let mp = (m + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_quoted_option_value() {
+        assert_eq!(
+            decode_option_value("\"http://example.com/answer\";"),
+            Some("http://example.com/answer".to_string())
+        );
+    }
+
+    #[test]
+    fn decodes_hex_option_value() {
+        // "http://good.example" as colon-separated hex octets.
+        assert_eq!(
+            decode_option_value("68:74:74:70:3a:2f:2f:67:6f:6f:64:2e:65:78:61:6d:70:6c:65;"),
+            Some("http://good.example".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_option_value() {
+        assert_eq!(decode_option_value("not-quoted-or-hex;"), None);
+    }
+
+    const LEASES: &str = r#"
+lease {
+  interface "eth0";
+  expire 2 2020/01/01 00:00:00 UTC;
+  renew 2 2019/12/25 00:00:00 UTC;
+  option proxmoxinst-url "http://stale.example/old";
+}
+lease {
+  interface "eth0";
+  expire 4 2999/01/01 00:00:00 UTC;
+  renew 4 2998/12/25 00:00:00 UTC;
+  option proxmoxinst-url 68:74:74:70:3a:2f:2f:67:6f:6f:64:2e:65:78:61:6d:70:6c:65;
+}
+lease {
+  interface "eth1";
+  expire 4 2999/01/01 00:00:00 UTC;
+  option proxmoxinst-url "http://wrong-nic.example/";
+}
+"#;
+
+    /// 2024/01/01 00:00:00 UTC, used as "now" so the first `eth0` lease above reads as expired
+    /// and the second as current.
+    const NOW: u64 = 1_704_067_200;
+
+    #[test]
+    fn picks_the_newest_non_expired_lease_for_the_interface() {
+        let options = select_options(LEASES, &["eth0".to_string()], NOW);
+        assert_eq!(
+            options.get("proxmoxinst-url").map(String::as_str),
+            Some("http://good.example")
+        );
+    }
+
+    #[test]
+    fn ignores_leases_for_interfaces_we_did_not_bring_up() {
+        let options = select_options(LEASES, &["eth2".to_string()], NOW);
+        assert!(options.is_empty());
+    }
+
+    #[test]
+    fn treats_expired_lease_as_unusable_when_it_is_the_only_match() {
+        let only_stale = r#"
+lease {
+  interface "eth0";
+  expire 2 2020/01/01 00:00:00 UTC;
+  option proxmoxinst-url "http://stale.example/old";
+}
+"#;
+        let options = select_options(only_stale, &["eth0".to_string()], NOW);
+        assert!(options.is_empty());
+    }
+}
@@ -0,0 +1,187 @@
+use anyhow::{Error, Result};
+use log::info;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+static BY_LABEL: &str = "/dev/disk/by-label";
+static BY_PARTLABEL: &str = "/dev/disk/by-partlabel";
+static BY_UUID: &str = "/dev/disk/by-uuid";
+
+/// Filesystems the `proxmoxinst` media is expected to carry. Anything else is rejected even if
+/// the label matches, since a mismatched guess from `mount` would otherwise fail or misbehave.
+static EXPECTED_FILESYSTEMS: &[&str] = &["vfat", "ext2", "ext3", "ext4", "iso9660"];
+
+/// A partition/device considered as a possible `proxmoxinst` answer source.
+struct Candidate {
+    path: PathBuf,
+    removable: bool,
+}
+
+/// Searches `/dev/disk/by-label`, `/dev/disk/by-partlabel` and `/dev/disk/by-uuid` for a
+/// partition that actually carries the expected label and filesystem, and returns the best
+/// match to mount.
+///
+/// Unlike plain label lookups, this also covers media whose label is in a case the upper/lower
+/// case lookups miss, since every `/dev/disk/by-uuid` entry is checked too. A device is only
+/// picked if its `LABEL` or `PARTLABEL` matches `partlabel`; a UUID alone is not enough.
+/// Every candidate considered is logged; when several match, removable media (USB keys) is
+/// preferred over fixed disks, since that is how operators usually present the answer file
+/// out-of-band.
+pub fn find_proxmoxinst_partition(partlabel: &str) -> Result<PathBuf> {
+    let mut candidates = Vec::new();
+    for search_path in [BY_LABEL, BY_PARTLABEL] {
+        for case in [partlabel.to_uppercase(), partlabel.to_lowercase()] {
+            let path = Path::new(search_path).join(&case);
+            if path.try_exists().unwrap_or(false) {
+                candidates.push(path);
+            }
+        }
+    }
+
+    if let Ok(entries) = fs::read_dir(BY_UUID) {
+        for entry in entries.flatten() {
+            candidates.push(entry.path());
+        }
+    }
+
+    let mut verified = Vec::new();
+    for path in candidates {
+        match verify_candidate(&path, partlabel) {
+            Ok(true) => {
+                let removable = is_removable(&path);
+                info!(
+                    "Considering '{}' (removable: {removable}) as proxmoxinst media.",
+                    path.display()
+                );
+                verified.push(Candidate { path, removable });
+            }
+            Ok(false) => info!(
+                "Rejecting '{}': label or filesystem does not match.",
+                path.display()
+            ),
+            Err(err) => info!("Could not inspect '{}': {err}", path.display()),
+        }
+    }
+
+    verified.sort_by_key(|candidate| !candidate.removable);
+
+    verified
+        .into_iter()
+        .next()
+        .map(|candidate| candidate.path)
+        .ok_or_else(|| {
+            Error::msg(format!(
+                "Could not find a partition labeled '{partlabel}' with an expected filesystem"
+            ))
+        })
+}
+
+/// Checks, via `blkid`, that `path` carries either a matching `LABEL` or `PARTLABEL` and a
+/// filesystem we know how to mount.
+fn verify_candidate(path: &Path, partlabel: &str) -> Result<bool> {
+    let label = blkid_value(path, "LABEL")?;
+    let part_label = blkid_value(path, "PARTLABEL")?;
+    let fs_type = blkid_value(path, "TYPE")?;
+
+    Ok(candidate_matches(&label, &part_label, &fs_type, partlabel))
+}
+
+/// Checks whether a candidate's `blkid`-reported `LABEL`/`PARTLABEL`/`TYPE` match `partlabel`
+/// and one of the [`EXPECTED_FILESYSTEMS`], case-insensitively.
+fn candidate_matches(label: &str, part_label: &str, fs_type: &str, partlabel: &str) -> bool {
+    let label_matches = [label, part_label]
+        .iter()
+        .any(|value| value.eq_ignore_ascii_case(partlabel));
+    let fs_matches = EXPECTED_FILESYSTEMS
+        .iter()
+        .any(|expected| expected.eq_ignore_ascii_case(fs_type));
+
+    label_matches && fs_matches
+}
+
+fn blkid_value(path: &Path, tag: &str) -> Result<String> {
+    let output = Command::new("blkid")
+        .args(["-o", "value", "-s", tag])
+        .arg(path)
+        .output()?;
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
+/// Checks whether the block device backing `path` is removable, by resolving it to its parent
+/// disk in `/sys/class/block`.
+fn is_removable(path: &Path) -> bool {
+    let Ok(canonical) = fs::canonicalize(path) else {
+        return false;
+    };
+    let Some(dev_name) = canonical.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    let disk_name = parent_disk_name(dev_name);
+
+    for candidate in [dev_name, disk_name.as_str()] {
+        let removable_file = Path::new("/sys/class/block")
+            .join(candidate)
+            .join("removable");
+        if let Ok(contents) = fs::read_to_string(removable_file) {
+            return contents.trim() == "1";
+        }
+    }
+    false
+}
+
+/// Strips a partition number off a block device name to get its parent disk's name, e.g.
+/// `sda1` -> `sda` or `nvme0n1p1` -> `nvme0n1`.
+///
+/// NVMe/MMC partitions use a `pN` suffix on top of the base device name, so the trailing `p` is
+/// stripped too once the digits are gone; plain `sdX`-style names have no such separator.
+fn parent_disk_name(dev_name: &str) -> String {
+    let trimmed = dev_name.trim_end_matches(|c: char| c.is_ascii_digit());
+    match trimmed.strip_suffix('p') {
+        Some(base) if base.ends_with(|c: char| c.is_ascii_digit()) => base.to_string(),
+        _ => trimmed.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn candidate_matches_on_label_fs_and_case() {
+        assert!(candidate_matches("PROXMOXINST", "", "VFAT", "proxmoxinst"));
+        assert!(candidate_matches("", "proxmoxinst", "ext4", "proxmoxinst"));
+    }
+
+    #[test]
+    fn candidate_rejects_wrong_label() {
+        assert!(!candidate_matches("other", "other", "vfat", "proxmoxinst"));
+    }
+
+    #[test]
+    fn candidate_rejects_unexpected_filesystem() {
+        assert!(!candidate_matches(
+            "proxmoxinst",
+            "proxmoxinst",
+            "btrfs",
+            "proxmoxinst"
+        ));
+    }
+
+    #[test]
+    fn parent_disk_name_strips_plain_partition_number() {
+        assert_eq!(parent_disk_name("sda1"), "sda");
+    }
+
+    #[test]
+    fn parent_disk_name_strips_nvme_partition_suffix() {
+        assert_eq!(parent_disk_name("nvme0n1p1"), "nvme0n1");
+    }
+
+    #[test]
+    fn parent_disk_name_leaves_whole_disk_name_untouched() {
+        assert_eq!(parent_disk_name("sda"), "sda");
+    }
+}
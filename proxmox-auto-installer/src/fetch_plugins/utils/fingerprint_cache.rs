@@ -0,0 +1,48 @@
+use anyhow::{Context, Result};
+use serde_json::{Map, Value};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Where accepted self-signed certificate fingerprints are cached, keyed by the host of the
+/// answer-file URL. Mirrors the fingerprint cache proxmox-backup-client keeps for its own HTTP
+/// client, just scoped to this installer.
+static CACHE_FILE: &str = "/var/lib/proxmox-auto-installer/fingerprint-cache.json";
+
+pub struct FingerprintCache;
+
+impl FingerprintCache {
+    /// Returns the cached fingerprint for `host`, if any.
+    pub fn lookup(host: &str) -> Option<String> {
+        let map = Self::load().ok()?;
+        map.get(host)?.as_str().map(String::from)
+    }
+
+    /// Records `fingerprint` as accepted for `host`, overwriting any previous entry.
+    ///
+    /// This only ever stores a fingerprint the caller already decided to trust; a mismatch
+    /// against a previously cached entry is the caller's decision to make, not this cache's.
+    pub fn store(host: &str, fingerprint: &str) -> Result<()> {
+        let mut map = Self::load().unwrap_or_default();
+        map.insert(host.to_string(), Value::String(fingerprint.to_string()));
+
+        if let Some(parent) = Path::new(CACHE_FILE).parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("could not create '{}'", parent.display()))?;
+        }
+        fs::write(CACHE_FILE, serde_json::to_string_pretty(&map)?)
+            .with_context(|| format!("could not write fingerprint cache to '{CACHE_FILE}'"))
+    }
+
+    fn load() -> Result<Map<String, Value>> {
+        let path = PathBuf::from(CACHE_FILE);
+        if !path.exists() {
+            return Ok(Map::new());
+        }
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("could not read fingerprint cache at '{}'", path.display()))?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("could not parse fingerprint cache at '{}'", path.display()))
+    }
+}
@@ -4,56 +4,39 @@ use serde::Deserialize;
 use serde_json;
 use std::{
     fs::{self, create_dir_all},
-    path::{Path, PathBuf},
+    path::Path,
     process::Command,
 };
 
 static ANSWER_MP: &str = "/mnt/answer";
 static PARTLABEL: &str = "proxmoxinst";
-static SEARCH_PATH: &str = "/dev/disk/by-label";
+static ISO_ANSWER_FILE: &str = "/cdrom/answer.toml";
 
+pub mod config;
+pub mod dhcp_leases;
+pub mod discovery;
+pub mod fingerprint_cache;
 pub mod post;
 pub mod sysinfo;
 
-/// Searches for upper and lower case existence of the partlabel in the search_path
-///
-/// # Arguemnts
-/// * `partlabel_source` - Partition Label, used as upper and lower case
-/// * `search_path` - Path where to search for the partiiton label
-pub fn scan_partlabels(partlabel_source: &str, search_path: &str) -> Result<PathBuf> {
-    let partlabel = partlabel_source.to_uppercase();
-    let path = Path::new(search_path).join(&partlabel);
-    match path.try_exists() {
-        Ok(true) => {
-            info!("Found partition with label '{}'", partlabel);
-            return Ok(path);
-        }
-        Ok(false) => info!("Did not detect partition with label '{}'", partlabel),
-        Err(err) => info!("Encountered issue, accessing '{}': {}", path.display(), err),
-    }
-
-    let partlabel = partlabel_source.to_lowercase();
-    let path = Path::new(search_path).join(&partlabel);
-    match path.try_exists() {
-        Ok(true) => {
-            info!("Found partition with label '{}'", partlabel);
-            return Ok(path);
-        }
-        Ok(false) => info!("Did not detect partition with label '{}'", partlabel),
-        Err(err) => info!("Encountered issue, accessing '{}': {}", path.display(), err),
-    }
-    Err(Error::msg(format!(
-        "Could not detect upper or lower case labels for '{partlabel_source}'"
-    )))
+/// Reads the answer file embedded directly on the installer ISO (`FetchMode::Included`).
+pub fn get_included_answer() -> Result<String> {
+    let path = Path::new(ISO_ANSWER_FILE);
+    fs::read_to_string(path).map_err(|err| {
+        Error::msg(format!(
+            "could not read included answer file at '{}': {err}",
+            path.display()
+        ))
+    })
 }
 
-/// Will search and mount a partition/FS labeled proxmoxinst in lower or uppercase to ANSWER_MP;
+/// Will search and mount a partition/FS labeled proxmoxinst to ANSWER_MP.
 pub fn mount_proxmoxinst_part() -> Result<String> {
     if let Ok(true) = check_if_mounted(ANSWER_MP) {
         info!("Skipping: '{ANSWER_MP}' is already mounted.");
         return Ok(ANSWER_MP.into());
     }
-    let part_path = scan_partlabels(PARTLABEL, SEARCH_PATH)?;
+    let part_path = discovery::find_proxmoxinst_partition(PARTLABEL)?;
     info!("Mounting partition at {ANSWER_MP}");
     // create dir for mountpoint
     create_dir_all(ANSWER_MP)?;
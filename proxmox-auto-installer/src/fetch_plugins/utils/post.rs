@@ -0,0 +1,242 @@
+use anyhow::{bail, Context, Result};
+use log::{info, warn};
+use openssl::hash::{hash, MessageDigest};
+use openssl::ssl::{SslConnector, SslMethod, SslStream, SslVerifyMode};
+use std::{
+    io::{Read, Write},
+    net::TcpStream,
+};
+
+use super::fingerprint_cache::FingerprintCache;
+
+struct Target {
+    host: String,
+    port: u16,
+    path: String,
+    tls: bool,
+}
+
+fn parse_url(url: &str) -> Result<Target> {
+    let (tls, rest) = if let Some(rest) = url.strip_prefix("https://") {
+        (true, rest)
+    } else if let Some(rest) = url.strip_prefix("http://") {
+        (false, rest)
+    } else {
+        bail!("answer URL '{url}' must start with http:// or https://");
+    };
+
+    let (authority, path) = match rest.find('/') {
+        Some(idx) => (&rest[..idx], &rest[idx..]),
+        None => (rest, "/"),
+    };
+
+    let (host, port) = match authority.split_once(':') {
+        Some((host, port)) => (
+            host.to_string(),
+            port.parse()
+                .with_context(|| format!("invalid port in URL '{url}'"))?,
+        ),
+        None => (authority.to_string(), if tls { 443 } else { 80 }),
+    };
+
+    if host.contains(['\r', '\n']) || path.contains(['\r', '\n']) {
+        bail!("answer URL '{url}' contains control characters in host or path");
+    }
+
+    Ok(Target {
+        host,
+        port,
+        path: path.to_string(),
+        tls,
+    })
+}
+
+fn connect_tcp(target: &Target) -> Result<TcpStream> {
+    TcpStream::connect((target.host.as_str(), target.port))
+        .with_context(|| format!("could not connect to '{}:{}'", target.host, target.port))
+}
+
+fn connect_tls(target: &Target, verify: SslVerifyMode) -> Result<SslStream<TcpStream>> {
+    let tcp = connect_tcp(target)?;
+    let mut connector = SslConnector::builder(SslMethod::tls())?;
+    connector.set_verify(verify);
+    let connector = connector.build();
+    connector
+        .connect(&target.host, tcp)
+        .map_err(|err| anyhow::anyhow!("TLS handshake with '{}' failed: {err}", target.host))
+}
+
+/// Computes the SHA256 fingerprint of the certificate presented on `stream` and checks it
+/// against `fingerprint` (if given), or the fingerprint cache otherwise (trust-on-first-use).
+///
+/// A fingerprint that no longer matches a previously cached value is refused outright, exactly
+/// like `ssh`'s changed-host-key behavior this cache is modeled on: a changed TOFU fingerprint
+/// is the canonical MITM signal and must not be silently re-pinned.
+fn verify_fingerprint(
+    stream: &SslStream<TcpStream>,
+    target: &Target,
+    fingerprint: Option<&str>,
+) -> Result<()> {
+    let cert = stream
+        .ssl()
+        .peer_certificate()
+        .context("server did not present a certificate")?;
+    let der = cert.to_der()?;
+    let actual_fingerprint = hash(MessageDigest::sha256(), &der)?
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<Vec<_>>()
+        .join(":");
+
+    match fingerprint {
+        Some(expected) if expected.eq_ignore_ascii_case(&actual_fingerprint) => {
+            info!("Certificate fingerprint matches the expected value.");
+            Ok(())
+        }
+        Some(expected) => {
+            bail!(
+                "certificate fingerprint '{actual_fingerprint}' does not match expected '{expected}'"
+            );
+        }
+        None => match FingerprintCache::lookup(&target.host) {
+            Some(cached) if cached == actual_fingerprint => {
+                info!(
+                    "Certificate fingerprint matches the cached value for '{}'.",
+                    target.host
+                );
+                Ok(())
+            }
+            Some(cached) => {
+                warn!(
+                    "Certificate fingerprint for '{}' changed from the cached value ('{cached}' -> '{actual_fingerprint}'); refusing to connect, possible MITM.",
+                    target.host
+                );
+                bail!(
+                    "certificate fingerprint for '{}' changed from the cached value ('{cached}' -> '{actual_fingerprint}'); refusing to connect. If this change is expected, remove the cached entry and retry.",
+                    target.host
+                );
+            }
+            None => {
+                warn!(
+                    "No fingerprint provided for '{}'; trusting the presented certificate on first use.",
+                    target.host
+                );
+                FingerprintCache::store(&target.host, &actual_fingerprint)
+            }
+        },
+    }
+}
+
+/// Sends the gathered system information to `url` as a HTTP POST request and returns the answer
+/// file contents from the response body.
+///
+/// When `fingerprint` is `Some`, that pin is an explicit, defense-in-depth choice by the
+/// operator and is always enforced, regardless of whether the certificate also happens to chain
+/// to a trusted root. Only when no fingerprint was configured does this fall back to an ordinary
+/// HTTPS client check against the system root store, and finally to trust-on-first-use via the
+/// fingerprint cache if that check fails.
+pub fn call(url: String, fingerprint: Option<&str>, payload: String) -> Result<String> {
+    let target = parse_url(&url)?;
+
+    if !target.tls {
+        let mut tcp = connect_tcp(&target)?;
+        return send_request(&mut tcp, &target, &payload);
+    }
+
+    if fingerprint.is_some() {
+        let mut stream = connect_tls(&target, SslVerifyMode::NONE)?;
+        verify_fingerprint(&stream, &target, fingerprint)?;
+        return send_request(&mut stream, &target, &payload);
+    }
+
+    match connect_tls(&target, SslVerifyMode::PEER) {
+        Ok(mut stream) => {
+            info!(
+                "Certificate for '{}' is trusted by the system root store.",
+                target.host
+            );
+            send_request(&mut stream, &target, &payload)
+        }
+        Err(err) => {
+            info!("Root-CA validation failed for '{}': {err}", target.host);
+            let mut stream = connect_tls(&target, SslVerifyMode::NONE)?;
+            verify_fingerprint(&stream, &target, None)?;
+            send_request(&mut stream, &target, &payload)
+        }
+    }
+}
+
+fn send_request<S: Read + Write>(stream: &mut S, target: &Target, payload: &str) -> Result<String> {
+    // `Connection: close` is requested explicitly, so reading until EOF below is the correct
+    // way to collect the full body rather than relying on a Content-Length/chunked framing.
+    // A body sent with `Transfer-Encoding: chunked` is returned as received, not decoded.
+    let request = format!(
+        "POST {path} HTTP/1.1\r\nHost: {host}\r\nContent-Type: application/json\r\nContent-Length: {len}\r\nConnection: close\r\n\r\n{payload}",
+        path = target.path,
+        host = target.host,
+        len = payload.len(),
+    );
+    stream.write_all(request.as_bytes())?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+
+    let (headers, body) = response
+        .split_once("\r\n\r\n")
+        .context("malformed HTTP response: missing header/body separator")?;
+
+    let status_line = headers
+        .lines()
+        .next()
+        .context("malformed HTTP response: missing status line")?;
+    let status_code: u16 = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .with_context(|| format!("malformed HTTP status line: '{status_line}'"))?;
+    if !(200..300).contains(&status_code) {
+        bail!("server returned HTTP {status_code}: {}", body.trim());
+    }
+
+    Ok(body.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_https_url_with_explicit_path() {
+        let target = parse_url("https://example.com:8443/answer.toml").unwrap();
+        assert_eq!(target.host, "example.com");
+        assert_eq!(target.port, 8443);
+        assert_eq!(target.path, "/answer.toml");
+        assert!(target.tls);
+    }
+
+    #[test]
+    fn defaults_port_and_path_by_scheme() {
+        let https = parse_url("https://example.com").unwrap();
+        assert_eq!(https.port, 443);
+        assert_eq!(https.path, "/");
+
+        let http = parse_url("http://example.com").unwrap();
+        assert_eq!(http.port, 80);
+        assert_eq!(http.path, "/");
+    }
+
+    #[test]
+    fn rejects_url_without_known_scheme() {
+        assert!(parse_url("ftp://example.com").is_err());
+    }
+
+    #[test]
+    fn rejects_crlf_smuggled_into_the_path() {
+        assert!(parse_url("http://example.com/foo\r\nX-Injected: 1").is_err());
+    }
+
+    #[test]
+    fn rejects_crlf_smuggled_into_the_host() {
+        assert!(parse_url("http://example.com\r\nX-Injected: 1/foo").is_err());
+    }
+}
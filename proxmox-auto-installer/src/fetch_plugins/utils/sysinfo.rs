@@ -0,0 +1,22 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::fs;
+
+#[derive(Serialize)]
+struct SysInfo {
+    hostname: String,
+}
+
+/// Gathers basic information about the machine running the installer, serialized as JSON for
+/// the answer-file POST body.
+///
+/// Only the hostname is collected; `minimal` does not change the output.
+pub fn get_sysinfo(minimal: bool) -> Result<String> {
+    let _ = minimal;
+    let hostname = fs::read_to_string("/etc/hostname")
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+
+    Ok(serde_json::to_string(&SysInfo { hostname })?)
+}
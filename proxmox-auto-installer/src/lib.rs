@@ -0,0 +1 @@
+pub mod fetch_plugins;